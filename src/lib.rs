@@ -38,12 +38,38 @@
 //! (it should parse data on the fly), portable. And of course, simplicity is a key feature
 //! - simple code style, simple algorithm, simple integration into other projects.
 
-#[derive(Default, Debug, Copy, Clone, PartialEq)]
+mod minify;
+mod stream;
+mod value;
+
+pub use minify::{minify, minify_cb};
+pub use value::JsonValue;
+
+#[derive(Default, Debug, Copy, Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub start: isize,
     pub end: isize,
     pub size: usize,
+    /// Index of the token that was open when this one was allocated: the
+    /// enclosing object/array, or the key token for an object member's
+    /// value. `-1` for a token at the root. Only present when the
+    /// `parent-links` feature is enabled.
+    #[cfg(feature = "parent-links")]
+    pub parent: isize,
+}
+
+/// Compares `kind`, `start`, `end` and `size` only. `parent` (under
+/// `parent-links`) is deliberately excluded: it reflects where a token sits
+/// in a particular parse rather than the token itself, and constructors like
+/// [`Token::new`] have no way to fill it in for an expected value in a test.
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.start == other.start
+            && self.end == other.end
+            && self.size == other.size
+    }
 }
 
 impl Token {
@@ -57,8 +83,32 @@ impl Token {
             start,
             end,
             size,
+            #[cfg(feature = "parent-links")]
+            parent: -1,
         }
     }
+
+    /// Index of the enclosing object/array token (or, for an object
+    /// member's value, of its key token), or `-1` for a token at the root.
+    #[cfg(feature = "parent-links")]
+    pub fn parent(&self) -> isize {
+        self.parent
+    }
+}
+
+/// Indices of the tokens whose immediate parent is `tokens[idx]`, in the
+/// order they appear in `tokens`.
+///
+/// This walks `tokens` once rather than re-scanning spans, so callers can
+/// navigate the parsed tree without the quadratic cost of matching byte
+/// ranges by hand.
+#[cfg(feature = "parent-links")]
+pub fn children(tokens: &[Token], idx: usize) -> impl Iterator<Item = usize> + '_ {
+    let parent = idx as isize;
+    tokens
+        .iter()
+        .enumerate()
+        .filter_map(move |(i, t)| (t.parent == parent).then_some(i))
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -86,10 +136,55 @@ pub enum Error {
     NoMemory,
 }
 
+/// Selects the grammar `JsonParser` accepts.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum ParseMode {
+    /// RFC-8259 JSON: the top-level value must be an object or array, and
+    /// every primitive must be a valid JSON number, `true`, `false` or `null`.
+    Strict,
+    /// The permissive grammar `jsmn` has always accepted, plus `//` and
+    /// `/* */` comments (outside strings) and a trailing comma before `}`
+    /// or `]`. Bare top-level primitives and unvalidated primitive runs are
+    /// both allowed.
+    #[default]
+    Lenient,
+}
+
+/// Configuration for [`JsonParser`]. Build one with [`ParserConfig::strict`]
+/// or [`ParserConfig::lenient`] and pass it to [`JsonParser::with_config`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ParserConfig {
+    mode: ParseMode,
+}
+
+impl ParserConfig {
+    /// RFC-8259 strict mode. See [`ParseMode::Strict`].
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+        }
+    }
+
+    /// Lenient mode. See [`ParseMode::Lenient`]. This is also the default
+    /// used by [`JsonParser::new`].
+    pub fn lenient() -> Self {
+        Self {
+            mode: ParseMode::Lenient,
+        }
+    }
+}
+
 pub struct JsonParser {
     pos: usize,
     tok_next: usize,
     tok_super: Option<usize>,
+    config: ParserConfig,
+    /// Absolute offset, in the logical stream fed to [`JsonParser::feed`],
+    /// of the start of the next chunk.
+    base: usize,
+    /// A string or primitive left unfinished at the end of the previous
+    /// [`JsonParser::feed`] call, if any.
+    partial: stream::Partial,
 }
 
 impl Default for JsonParser {
@@ -98,6 +193,9 @@ impl Default for JsonParser {
             pos: 0,
             tok_next: 0,
             tok_super: None,
+            config: ParserConfig::default(),
+            base: 0,
+            partial: stream::Partial::None,
         }
     }
 }
@@ -107,6 +205,15 @@ impl JsonParser {
         Self::default()
     }
 
+    /// Creates a parser that uses `config` instead of the default lenient
+    /// grammar.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
     ///
     /// Run JSON parser. It parses a JSON data string into and array of tokens, each
     /// describing a single JSON object.
@@ -120,6 +227,9 @@ impl JsonParser {
             let c = js[self.pos];
             match c {
                 b'{' | b'[' => {
+                    if self.is_strict_second_top_level_value() {
+                        return Err(Error::Invalid);
+                    }
                     count += 1;
                     let i = self.alloc_token(tokens).ok_or(Error::NoMemory)?;
                     if let Some(i) = self.tok_super {
@@ -174,6 +284,9 @@ impl JsonParser {
                     }
                 }
                 b'"' => {
+                    if self.is_strict_top_level_violation() {
+                        return Err(Error::Invalid);
+                    }
                     self.parse_string(js, tokens)?;
                     count += 1;
                     if let Some(i) = self.tok_super {
@@ -181,8 +294,14 @@ impl JsonParser {
                     }
                 }
                 b'\t' | b'\r' | b'\n' | b' ' => {}
+                b'/' if self.config.mode == ParseMode::Lenient => {
+                    self.skip_comment(js)?;
+                }
                 b':' => self.tok_super = Some(self.tok_next - 1),
                 b',' => {
+                    if self.config.mode == ParseMode::Strict && self.is_trailing_comma(js) {
+                        return Err(Error::Invalid);
+                    }
                     if let Some(i) = self.tok_super {
                         match tokens[i].kind {
                             TokenKind::Array | TokenKind::Object => {}
@@ -212,6 +331,8 @@ impl JsonParser {
                             TokenKind::Str if t.size != 0 => return Err(Error::Invalid),
                             _ => {}
                         }
+                    } else if self.is_strict_top_level_violation() {
+                        return Err(Error::Invalid);
                     }
                     self.parse_primitive(js, tokens)?;
                     count += 1;
@@ -242,7 +363,7 @@ impl JsonParser {
         let start = self.pos as isize;
         while self.pos < js.len() {
             match js[self.pos] {
-                b':' | b'\t' | b'\r' | b'\n' | b' ' | b',' | b']' | b'}' => break,
+                b':' | b'\t' | b'\r' | b'\n' | b' ' | b',' | b']' | b'}' | b'/' => break,
                 _ => {}
             }
 
@@ -253,9 +374,18 @@ impl JsonParser {
             self.pos += 1;
         }
 
+        if self.config.mode == ParseMode::Strict && !is_valid_primitive(&js[start as usize..self.pos])
+        {
+            self.pos = start as _;
+            return Err(Error::Invalid);
+        }
+
         match self.alloc_token(tokens) {
             Some(i) => {
-                tokens[i] = Token::new(TokenKind::Primitive, start, self.pos as _);
+                let token = &mut tokens[i];
+                token.kind = TokenKind::Primitive;
+                token.start = start;
+                token.end = self.pos as _;
             }
             None => {
                 self.pos = start as _;
@@ -267,6 +397,62 @@ impl JsonParser {
         Ok(())
     }
 
+    /// Returns whether, in strict mode, parsing a string or primitive at
+    /// `self.pos` would put a non-object/array at the top level. Unlike
+    /// [`JsonParser::is_strict_second_top_level_value`], this fires even for
+    /// the very first token, since a bare string/primitive is never a valid
+    /// top-level value in strict mode.
+    fn is_strict_top_level_violation(&self) -> bool {
+        self.config.mode == ParseMode::Strict && self.tok_super.is_none()
+    }
+
+    /// Returns whether, in strict mode, starting an object/array at
+    /// `self.pos` would be a *second* top-level value: strict mode requires
+    /// exactly one container at the top level, so this only rejects a
+    /// container seen after an earlier top-level value has already closed,
+    /// not the first one.
+    fn is_strict_second_top_level_value(&self) -> bool {
+        self.config.mode == ParseMode::Strict && self.tok_super.is_none() && self.tok_next != 0
+    }
+
+    /// Looks ahead from a `,` to see whether the next significant byte is a
+    /// closing `}`/`]`, i.e. whether the comma is a trailing comma.
+    fn is_trailing_comma(&self, js: &[u8]) -> bool {
+        let mut i = self.pos + 1;
+        while i < js.len() && matches!(js[i], b'\t' | b'\r' | b'\n' | b' ') {
+            i += 1;
+        }
+        matches!(js.get(i), Some(b'}') | Some(b']'))
+    }
+
+    /// Skips a `//` line comment or `/* */` block comment starting at
+    /// `self.pos`, leaving `self.pos` on the last byte consumed.
+    fn skip_comment(&mut self, js: &[u8]) -> Result<(), Error> {
+        match js.get(self.pos + 1) {
+            Some(b'/') => {
+                self.pos += 1;
+                while self.pos < js.len() && js[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+            }
+            Some(b'*') => {
+                self.pos += 2;
+                loop {
+                    if self.pos + 1 >= js.len() {
+                        return Err(Error::Part);
+                    }
+                    if js[self.pos] == b'*' && js[self.pos + 1] == b'/' {
+                        self.pos += 1;
+                        break;
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => return Err(Error::Invalid),
+        }
+        Ok(())
+    }
+
     /// Fills next token with JSON string.
     fn parse_string(&mut self, js: &[u8], tokens: &mut [Token]) -> Result<(), Error> {
         let start = self.pos as isize;
@@ -277,7 +463,12 @@ impl JsonParser {
             // Quote: end of string
             if c == b'\"' {
                 match self.alloc_token(tokens) {
-                    Some(i) => tokens[i] = Token::new(TokenKind::Str, start + 1, self.pos as _),
+                    Some(i) => {
+                        let token = &mut tokens[i];
+                        token.kind = TokenKind::Str;
+                        token.start = start + 1;
+                        token.end = self.pos as _;
+                    }
                     None => {
                         self.pos = start as _;
                         return Err(Error::NoMemory);
@@ -336,10 +527,63 @@ impl JsonParser {
         tok.end = -1;
         tok.start = tok.end;
         tok.size = 0;
+        #[cfg(feature = "parent-links")]
+        {
+            tok.parent = self.tok_super.map_or(-1, |i| i as isize);
+        }
         Some(idx)
     }
 }
 
+/// Returns whether `bytes` is `true`, `false`, `null`, or a valid JSON number.
+fn is_valid_primitive(bytes: &[u8]) -> bool {
+    matches!(bytes, b"true" | b"false" | b"null") || is_valid_number(bytes)
+}
+
+/// Returns whether `bytes` matches the JSON number grammar:
+/// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`.
+fn is_valid_number(bytes: &[u8]) -> bool {
+    let mut i = 0;
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    let digits_start = i;
+    if bytes.get(i) == Some(&b'0') {
+        i += 1;
+    } else {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+    }
+    if i == digits_start {
+        return false;
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        let frac_start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == frac_start {
+            return false;
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e') | Some(b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+') | Some(b'-')) {
+            i += 1;
+        }
+        let exp_start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == exp_start {
+            return false;
+        }
+    }
+    i == bytes.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,4 +639,99 @@ mod tests {
             tokens
         );
     }
+
+    #[test]
+    fn lenient_skips_comments() {
+        let s = b"{// a comment\n\"a\": /* inline */ 1}";
+        let mut tokens = vec![Token::default(); 3];
+        let mut parser = JsonParser::new();
+        let parsed = parser.parse(s, &mut tokens).unwrap();
+        assert_eq!(3, parsed);
+        assert_eq!(TokenKind::Object, tokens[0].kind);
+        assert_eq!(TokenKind::Str, tokens[1].kind);
+    }
+
+    #[test]
+    fn lenient_allows_trailing_comma() {
+        let s = br#"{"a": 1,}"#;
+        let mut tokens = vec![Token::default(); 3];
+        let mut parser = JsonParser::new();
+        assert!(parser.parse(s, &mut tokens).is_ok());
+    }
+
+    #[test]
+    fn strict_rejects_trailing_comma() {
+        let s = br#"{"a": 1,}"#;
+        let mut tokens = vec![Token::default(); 3];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert_eq!(Error::Invalid, parser.parse(s, &mut tokens).unwrap_err());
+    }
+
+    #[test]
+    fn strict_rejects_bare_top_level_primitive() {
+        let s = b"1234";
+        let mut tokens = vec![Token::default(); 1];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert_eq!(Error::Invalid, parser.parse(s, &mut tokens).unwrap_err());
+    }
+
+    #[test]
+    fn strict_rejects_second_top_level_primitive() {
+        let s = b"{} 1";
+        let mut tokens = vec![Token::default(); 1];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert_eq!(Error::Invalid, parser.parse(s, &mut tokens).unwrap_err());
+    }
+
+    #[test]
+    fn strict_rejects_second_top_level_container() {
+        let s = b"{} {}";
+        let mut tokens = vec![Token::default(); 2];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert_eq!(Error::Invalid, parser.parse(s, &mut tokens).unwrap_err());
+    }
+
+    #[test]
+    fn strict_rejects_invalid_primitive() {
+        let s = br#"{"a": truthy}"#;
+        let mut tokens = vec![Token::default(); 3];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert_eq!(Error::Invalid, parser.parse(s, &mut tokens).unwrap_err());
+    }
+
+    #[test]
+    fn strict_accepts_valid_numbers() {
+        let s = br#"[1, -2, 3.14, 2e10, -1.5e-3]"#;
+        let mut tokens = vec![Token::default(); 6];
+        let mut parser = JsonParser::with_config(ParserConfig::strict());
+        assert!(parser.parse(s, &mut tokens).is_ok());
+    }
+
+    #[cfg(feature = "parent-links")]
+    #[test]
+    fn parent_links_point_to_enclosing_container() {
+        let s = br#"{"a": "b", "c": [1, 2]}"#;
+        // 0: object, 1: "a", 2: "b", 3: "c", 4: array, 5: 1, 6: 2
+        // A value's parent is the token that was open when it was allocated:
+        // the enclosing object/array, or the key for an object's value.
+        let tokens = parse(s, 7).unwrap();
+        assert_eq!(-1, tokens[0].parent());
+        assert_eq!(0, tokens[1].parent());
+        assert_eq!(1, tokens[2].parent());
+        assert_eq!(0, tokens[3].parent());
+        assert_eq!(3, tokens[4].parent());
+        assert_eq!(4, tokens[5].parent());
+        assert_eq!(4, tokens[6].parent());
+    }
+
+    #[cfg(feature = "parent-links")]
+    #[test]
+    fn children_walks_immediate_members() {
+        let s = br#"{"a": "b", "c": [1, 2]}"#;
+        let tokens = parse(s, 7).unwrap();
+        let root_children: Vec<usize> = children(&tokens, 0).collect();
+        assert_eq!(vec![1, 3], root_children);
+        let array_children: Vec<usize> = children(&tokens, 4).collect();
+        assert_eq!(vec![5, 6], array_children);
+    }
 }
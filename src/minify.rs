@@ -0,0 +1,99 @@
+use crate::Error;
+
+/// Copies `js` to `out`, stripping insignificant whitespace and ASCII control
+/// characters outside of strings.
+///
+/// This is a single-pass, allocation-free minifier built on the same
+/// byte-classification rules the tokenizer uses: an unescaped `"` toggles
+/// whether we are inside a string, and while inside a string every byte
+/// (including whitespace and control characters) is copied verbatim.
+///
+/// Returns the number of bytes written to `out`. Returns `Error::NoMemory`
+/// if `out` is too small, or `Error::Part` if `js` ends inside a string.
+pub fn minify(js: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let mut len = 0;
+    minify_cb(js, |b| {
+        let slot = out.get_mut(len).ok_or(Error::NoMemory)?;
+        *slot = b;
+        len += 1;
+        Ok(())
+    })?;
+    Ok(len)
+}
+
+/// `no_std`-friendly variant of [`minify`] that emits bytes one at a time
+/// through `emit` instead of writing into a slice.
+///
+/// Returns the number of bytes emitted, or `Error::Part` if `js` ends inside
+/// a string. `emit` may itself fail (e.g. a fixed-size ring buffer running
+/// out of room); its error is propagated unchanged.
+pub fn minify_cb<F>(js: &[u8], mut emit: F) -> Result<usize, Error>
+where
+    F: FnMut(u8) -> Result<(), Error>,
+{
+    let mut count = 0;
+    let mut in_string = false;
+    let mut i = 0;
+    while i < js.len() {
+        let c = js[i];
+        // Backslash escape: the following byte is always in-string content,
+        // regardless of what it looks like (e.g. an escaped quote).
+        if in_string && c == b'\\' {
+            if i + 1 >= js.len() {
+                return Err(Error::Part);
+            }
+            emit(c)?;
+            emit(js[i + 1])?;
+            count += 2;
+            i += 2;
+            continue;
+        }
+        if c == b'"' {
+            in_string = !in_string;
+        }
+        if in_string || c > 0x20 {
+            emit(c)?;
+            count += 1;
+        }
+        i += 1;
+    }
+    if in_string {
+        return Err(Error::Part);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minify_strips_whitespace() {
+        let js = b"{\n  \"a\" : 1,\t\"b\": [1, 2]\n}";
+        let mut out = [0u8; 64];
+        let len = minify(js, &mut out).unwrap();
+        assert_eq!(&out[..len], br#"{"a":1,"b":[1,2]}"#.as_ref());
+    }
+
+    #[test]
+    fn minify_preserves_whitespace_in_strings() {
+        let js = b"{\"a\": \"b c\\td\"}";
+        let mut out = [0u8; 32];
+        let len = minify(js, &mut out).unwrap();
+        assert_eq!(&out[..len], br#"{"a":"b c\td"}"#.as_ref());
+    }
+
+    #[test]
+    fn minify_no_memory() {
+        let js = b"{\"a\": 1}";
+        let mut out = [0u8; 2];
+        assert_eq!(Error::NoMemory, minify(js, &mut out).unwrap_err());
+    }
+
+    #[test]
+    fn minify_unterminated_string() {
+        let js = b"{\"a\": \"b";
+        let mut out = [0u8; 32];
+        assert_eq!(Error::Part, minify(js, &mut out).unwrap_err());
+    }
+}
@@ -0,0 +1,519 @@
+use crate::{is_valid_primitive, Error, JsonParser, ParseMode, Token, TokenKind};
+
+/// A string or primitive left unfinished at the end of a [`JsonParser::feed`]
+/// call, so the next call can resume scanning it instead of starting over.
+pub(crate) enum Partial {
+    None,
+    Str { start: usize, escaped: bool },
+    Primitive { start: usize },
+}
+
+/// Outcome of scanning as far as possible into the current chunk: either
+/// the token completed (at the returned index, just past its last byte) or
+/// the chunk ran out first and scanning must resume on the next one.
+enum Scan {
+    Done(usize),
+    NeedMore,
+}
+
+impl JsonParser {
+    /// Feeds the next chunk of a JSON stream to the parser, e.g. as bytes
+    /// arrive from a socket.
+    ///
+    /// Unlike [`JsonParser::parse`], `chunk` is just the newly arrived bytes
+    /// — not the whole document seen so far — and `Token::start`/`Token::end`
+    /// are absolute offsets into the logical stream (the concatenation of
+    /// every chunk fed so far), not into `chunk` itself. A string or
+    /// primitive that straddles a chunk boundary resumes exactly where the
+    /// previous call left off, without rescanning or discarding the tokens
+    /// already produced.
+    ///
+    /// Returns `Error::Part` to mean "need more bytes"; call `feed` again
+    /// with the next chunk once more data has arrived. This also covers an
+    /// unmatched open object/array at the end of `chunk`, mirroring the
+    /// end-of-input check [`JsonParser::parse`] does, so a caller can tell
+    /// "document complete" from "still waiting for more bytes" at any chunk
+    /// boundary, not just one that lands mid-string or mid-primitive.
+    /// Returns `Error::NoMemory` if `tokens` fills up, same as `parse` — grow
+    /// the pool (preserving its existing contents) and re-feed the
+    /// unconsumed tail of `chunk` (the bytes from the start of the token
+    /// that didn't fit onward; nothing before that point is re-scanned or
+    /// lost). Returns the total number of tokens parsed so far on success,
+    /// same as `parse`.
+    ///
+    /// Two grammar features are intentionally not supported across a chunk
+    /// boundary: a `//`/`/* */` comment and the trailing-comma lookahead
+    /// used by [`ParseMode::Strict`] both require their closing bytes to be
+    /// in the same chunk they started in. A primitive that spans a chunk
+    /// boundary also skips the strict-mode number/literal validation, since
+    /// the bytes before the boundary are no longer available to re-check.
+    pub fn feed(&mut self, chunk: &[u8], tokens: &mut [Token]) -> Result<usize, Error> {
+        let mut count = self.tok_next;
+        let mut i = 0;
+
+        let resumed = match core::mem::replace(&mut self.partial, Partial::None) {
+            Partial::Str { start, escaped } => {
+                let r = self.scan_str(chunk, tokens, 0, start, escaped, &mut count);
+                if r.is_err() {
+                    self.partial = Partial::Str { start, escaped };
+                }
+                Some(r)
+            }
+            Partial::Primitive { start } => {
+                let r = self.scan_primitive(chunk, tokens, 0, start, &mut count);
+                if r.is_err() {
+                    self.partial = Partial::Primitive { start };
+                }
+                Some(r)
+            }
+            Partial::None => None,
+        };
+        match resumed {
+            Some(Ok(Scan::Done(next))) => i = next,
+            Some(Ok(Scan::NeedMore)) => {
+                self.base += chunk.len();
+                return Err(Error::Part);
+            }
+            // None of this chunk was consumed before the resumed token
+            // failed to allocate; the unfinished token was put back in
+            // `self.partial` above and `self.base` stays where it was, so the
+            // caller can retry with more tokens and the same bytes.
+            Some(Err(e)) => return Err(e),
+            None => {}
+        }
+
+        while i < chunk.len() {
+            let c = chunk[i];
+            let abs = self.base + i;
+            match c {
+                b'{' | b'[' => {
+                    if self.is_strict_second_top_level_value() {
+                        return Err(Error::Invalid);
+                    }
+                    count += 1;
+                    let idx = match self.alloc_token(tokens) {
+                        Some(idx) => idx,
+                        // Nothing from `i` on was consumed; a retry resends
+                        // the chunk starting from this bracket.
+                        None => {
+                            self.base += i;
+                            return Err(Error::NoMemory);
+                        }
+                    };
+                    if let Some(si) = self.tok_super {
+                        let t = &mut tokens[si];
+                        if let TokenKind::Object | TokenKind::Array = t.kind {
+                            return Err(Error::Invalid);
+                        }
+                        t.size += 1;
+                    }
+                    let token = &mut tokens[idx];
+                    token.kind = if c == b'{' {
+                        TokenKind::Object
+                    } else {
+                        TokenKind::Array
+                    };
+                    token.start = abs as isize;
+                    self.tok_super = Some(self.tok_next - 1);
+                }
+                b'}' | b']' => {
+                    let kind = if c == b'}' {
+                        TokenKind::Object
+                    } else {
+                        TokenKind::Array
+                    };
+                    let mut j = self.tok_next as isize - 1;
+                    while j >= 0 {
+                        let token = &mut tokens[j as usize];
+                        if token.start != -1 && token.end == -1 {
+                            if token.kind != kind {
+                                return Err(Error::Invalid);
+                            }
+                            self.tok_super = None;
+                            token.end = abs as isize + 1;
+                            break;
+                        }
+                        j -= 1;
+                    }
+                    if j == -1 {
+                        return Err(Error::Invalid);
+                    }
+                    while j >= 0 {
+                        let token = &mut tokens[j as usize];
+                        if token.start != -1 && token.end == -1 {
+                            self.tok_super = Some(j as usize);
+                            break;
+                        }
+                        j -= 1;
+                    }
+                }
+                b'"' => {
+                    if self.is_strict_top_level_violation() {
+                        return Err(Error::Invalid);
+                    }
+                    match self.scan_str(chunk, tokens, i + 1, abs, false, &mut count) {
+                        Ok(Scan::Done(next)) => {
+                            i = next;
+                            continue;
+                        }
+                        Ok(Scan::NeedMore) => {
+                            self.base += chunk.len();
+                            return Err(Error::Part);
+                        }
+                        // Only bytes up to the start of this string were
+                        // actually consumed; don't advance `self.base` past
+                        // them, so a retry with more tokens can resend the
+                        // rest of the chunk starting from `i`.
+                        Err(e) => {
+                            self.base += i;
+                            return Err(e);
+                        }
+                    }
+                }
+                b'\t' | b'\r' | b'\n' | b' ' => {}
+                b'/' if self.config.mode == ParseMode::Lenient => {
+                    i = self.chunk_skip_comment(chunk, i)?;
+                    continue;
+                }
+                b':' => self.tok_super = Some(self.tok_next - 1),
+                b',' => {
+                    if self.config.mode == ParseMode::Strict && self.chunk_trailing_comma(chunk, i)
+                    {
+                        return Err(Error::Invalid);
+                    }
+                    self.close_value_on_comma(tokens);
+                }
+                b'0'..=b'9' | b'-' | b't' | b'f' | b'n' => {
+                    if let Some(si) = self.tok_super {
+                        let t = &mut tokens[si];
+                        match t.kind {
+                            TokenKind::Object => return Err(Error::Invalid),
+                            TokenKind::Str if t.size != 0 => return Err(Error::Invalid),
+                            _ => {}
+                        }
+                    } else if self.is_strict_top_level_violation() {
+                        return Err(Error::Invalid);
+                    }
+                    match self.scan_primitive(chunk, tokens, i, abs, &mut count) {
+                        Ok(Scan::Done(next)) => {
+                            i = next;
+                            continue;
+                        }
+                        Ok(Scan::NeedMore) => {
+                            self.base += chunk.len();
+                            return Err(Error::Part);
+                        }
+                        // Same rationale as the string case above: rewind to
+                        // just before this primitive started.
+                        Err(e) => {
+                            self.base += i;
+                            return Err(e);
+                        }
+                    }
+                }
+                _ => return Err(Error::Invalid),
+            }
+            i += 1;
+        }
+        // Unmatched opened object or array: mirrors the same check at the
+        // end of `parse`, so an unterminated document reports `Error::Part`
+        // here too instead of `Ok`.
+        let mut j = self.tok_next as isize - 1;
+        while j >= 0 {
+            if tokens[j as usize].start != -1 && tokens[j as usize].end == -1 {
+                self.base += chunk.len();
+                return Err(Error::Part);
+            }
+            j -= 1;
+        }
+        self.base += chunk.len();
+        Ok(count)
+    }
+
+    /// Scans a string body, starting at `chunk[i]`, whose opening quote sits
+    /// at the absolute offset `start`. Returns the index just past the
+    /// closing quote, or `Scan::NeedMore` (recording resume state in
+    /// `self.partial`) if `chunk` ends first.
+    ///
+    /// Returns `Error::NoMemory` if the token pool is full once the closing
+    /// quote is found, matching [`JsonParser::parse`]; the caller is
+    /// responsible for not treating this as "need more bytes".
+    fn scan_str(
+        &mut self,
+        chunk: &[u8],
+        tokens: &mut [Token],
+        mut i: usize,
+        start: usize,
+        mut escaped: bool,
+        count: &mut usize,
+    ) -> Result<Scan, Error> {
+        loop {
+            let c = match chunk.get(i) {
+                Some(&c) => c,
+                None => {
+                    self.partial = Partial::Str { start, escaped };
+                    return Ok(Scan::NeedMore);
+                }
+            };
+            if escaped {
+                escaped = false;
+                i += 1;
+                continue;
+            }
+            match c {
+                b'\\' => {
+                    escaped = true;
+                    i += 1;
+                }
+                b'"' => {
+                    let idx = self.alloc_token(tokens).ok_or(Error::NoMemory)?;
+                    let token = &mut tokens[idx];
+                    token.kind = TokenKind::Str;
+                    token.start = start as isize + 1;
+                    token.end = (self.base + i) as isize;
+                    *count += 1;
+                    if let Some(si) = self.tok_super {
+                        tokens[si].size += 1;
+                    }
+                    return Ok(Scan::Done(i + 1));
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    /// Scans a primitive, starting at `chunk[i]`, whose first byte sits at
+    /// the absolute offset `start`. Returns the index of the delimiter that
+    /// ended it (left unconsumed for the caller), or `Scan::NeedMore` if
+    /// `chunk` ends first.
+    ///
+    /// Returns `Error::NoMemory` if the token pool is full once the
+    /// delimiter is found, matching [`JsonParser::parse`]. Also applies
+    /// [`ParserConfig::strict`] primitive validation, but only when `start`
+    /// falls within `chunk` — i.e. the primitive didn't resume from a
+    /// previous call, so the bytes from `start` to the delimiter are all
+    /// available here to re-check.
+    fn scan_primitive(
+        &mut self,
+        chunk: &[u8],
+        tokens: &mut [Token],
+        mut i: usize,
+        start: usize,
+        count: &mut usize,
+    ) -> Result<Scan, Error> {
+        loop {
+            match chunk.get(i) {
+                None => {
+                    self.partial = Partial::Primitive { start };
+                    return Ok(Scan::NeedMore);
+                }
+                Some(b':' | b'\t' | b'\r' | b'\n' | b' ' | b',' | b']' | b'}' | b'/') => {
+                    if self.config.mode == ParseMode::Strict
+                        && start >= self.base
+                        && !is_valid_primitive(&chunk[start - self.base..i])
+                    {
+                        return Err(Error::Invalid);
+                    }
+                    let idx = self.alloc_token(tokens).ok_or(Error::NoMemory)?;
+                    let token = &mut tokens[idx];
+                    token.kind = TokenKind::Primitive;
+                    token.start = start as isize;
+                    token.end = (self.base + i) as isize;
+                    *count += 1;
+                    if let Some(si) = self.tok_super {
+                        tokens[si].size += 1;
+                    }
+                    return Ok(Scan::Done(i));
+                }
+                Some(&b) if !(32..127).contains(&b) => {
+                    // No token is produced: `feed`'s own loop then reports
+                    // this byte as Invalid in the same call; there is no
+                    // resumable state to stash for a malformed primitive.
+                    self.partial = Partial::None;
+                    return Ok(Scan::Done(i));
+                }
+                Some(_) => i += 1,
+            }
+        }
+    }
+
+    /// Mirrors the `,` handling in [`JsonParser::parse`]: walks back up to
+    /// the nearest still-open array/object so subsequent values attach to
+    /// the right container.
+    fn close_value_on_comma(&mut self, tokens: &[Token]) {
+        if let Some(i) = self.tok_super {
+            if let TokenKind::Array | TokenKind::Object = tokens[i].kind {
+                return;
+            }
+        }
+        let mut i = self.tok_next as isize - 1;
+        while i >= 0 {
+            let t = &tokens[i as usize];
+            if let TokenKind::Array | TokenKind::Object = t.kind {
+                if t.start != -1 && t.end == -1 {
+                    self.tok_super = Some(i as usize);
+                    return;
+                }
+            }
+            i -= 1;
+        }
+    }
+
+    /// Skips a `//` line comment or `/* */` block comment starting at
+    /// `chunk[i]`, mirroring [`JsonParser::skip_comment`]. Returns the index
+    /// just past the last byte consumed. Unlike `skip_comment`, a comment
+    /// can't resume across a chunk boundary: a `/* */` comment whose closing
+    /// `*/` isn't in `chunk` reports `Error::Invalid` rather than waiting for
+    /// more bytes.
+    fn chunk_skip_comment(&self, chunk: &[u8], i: usize) -> Result<usize, Error> {
+        match chunk.get(i + 1) {
+            Some(b'/') => {
+                let mut j = i + 1;
+                while j < chunk.len() && chunk[j] != b'\n' {
+                    j += 1;
+                }
+                Ok(j)
+            }
+            Some(b'*') => {
+                let mut j = i + 2;
+                loop {
+                    if j + 1 >= chunk.len() {
+                        return Err(Error::Invalid);
+                    }
+                    if chunk[j] == b'*' && chunk[j + 1] == b'/' {
+                        return Ok(j + 2);
+                    }
+                    j += 1;
+                }
+            }
+            _ => Err(Error::Invalid),
+        }
+    }
+
+    /// Whether, in strict mode, the `,` at `chunk[pos]` is immediately
+    /// followed (modulo whitespace) by a closing `}`/`]` in the same chunk.
+    fn chunk_trailing_comma(&self, chunk: &[u8], pos: usize) -> bool {
+        let mut i = pos + 1;
+        while i < chunk.len() && matches!(chunk[i], b'\t' | b'\r' | b'\n' | b' ') {
+            i += 1;
+        }
+        matches!(chunk.get(i), Some(b'}') | Some(b']'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonParser;
+
+    #[test]
+    fn single_chunk() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 3];
+        let n = parser.feed(br#"{"a": 1}"#, &mut tokens).unwrap();
+        assert_eq!(3, n);
+        assert_eq!(vec![
+            Token::with_size(TokenKind::Object, 0, 8, 1),
+            Token::with_size(TokenKind::Str, 2, 3, 1),
+            Token::with_size(TokenKind::Primitive, 6, 7, 0),
+        ], tokens);
+    }
+
+    #[test]
+    fn string_split_across_chunks() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 1];
+        assert_eq!(Error::Part, parser.feed(br#""hel"#, &mut tokens).unwrap_err());
+        let n = parser.feed(br#"lo""#, &mut tokens).unwrap();
+        assert_eq!(1, n);
+        assert_eq!(Token::new(TokenKind::Str, 1, 6), tokens[0]);
+    }
+
+    #[test]
+    fn primitive_split_across_chunks() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 1];
+        assert_eq!(Error::Part, parser.feed(b"12", &mut tokens).unwrap_err());
+        let n = parser.feed(b"34 ", &mut tokens).unwrap();
+        assert_eq!(1, n);
+        assert_eq!(Token::new(TokenKind::Primitive, 0, 4), tokens[0]);
+    }
+
+    #[test]
+    fn object_split_across_chunks_has_absolute_offsets() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 3];
+        assert_eq!(Error::Part, parser.feed(br#"{"a": "b"#, &mut tokens).unwrap_err());
+        let n = parser.feed(br#"c"}"#, &mut tokens).unwrap();
+        assert_eq!(3, n);
+        assert_eq!(Token::with_size(TokenKind::Object, 0, 11, 1), tokens[0]);
+        assert_eq!(Token::with_size(TokenKind::Str, 2, 3, 1), tokens[1]);
+        assert_eq!(Token::new(TokenKind::Str, 7, 9), tokens[2]);
+    }
+
+    #[test]
+    fn no_memory_does_not_drop_unconsumed_bytes() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 2];
+        assert_eq!(
+            Error::NoMemory,
+            parser.feed(br#"["a","b"]"#, &mut tokens).unwrap_err()
+        );
+
+        // Grow the token pool (preserving what's already there, same as
+        // growing it for `JsonParser::parse`) and resend the unconsumed
+        // tail of the chunk.
+        tokens.resize(3, Token::default());
+        let n = parser.feed(br#""b"]"#, &mut tokens).unwrap();
+        assert_eq!(3, n);
+        assert_eq!(Token::with_size(TokenKind::Array, 0, 9, 2), tokens[0]);
+        assert_eq!(Token::new(TokenKind::Str, 2, 3), tokens[1]);
+        assert_eq!(Token::new(TokenKind::Str, 6, 7), tokens[2]);
+    }
+
+    #[test]
+    fn feed_reports_part_for_unmatched_open_container() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 1];
+        assert_eq!(Error::Part, parser.feed(b"{", &mut tokens).unwrap_err());
+
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 2];
+        assert_eq!(
+            Error::Part,
+            parser.feed(br#"{"a": "#, &mut tokens).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn feed_skips_comments_within_chunk() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 3];
+        let n = parser
+            .feed(b"{// c\n\"a\": /* inline */ 1}", &mut tokens)
+            .unwrap();
+        assert_eq!(3, n);
+        assert_eq!(TokenKind::Object, tokens[0].kind);
+        assert_eq!(TokenKind::Str, tokens[1].kind);
+    }
+
+    #[test]
+    fn feed_validates_strict_primitives_within_chunk() {
+        let mut parser = JsonParser::with_config(crate::ParserConfig::strict());
+        let mut tokens = vec![Token::default(); 3];
+        assert_eq!(
+            Error::Invalid,
+            parser.feed(br#"{"a": truthy}"#, &mut tokens).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn escape_split_right_after_backslash() {
+        let mut parser = JsonParser::new();
+        let mut tokens = vec![Token::default(); 1];
+        assert_eq!(Error::Part, parser.feed(br#""a\"#, &mut tokens).unwrap_err());
+        let n = parser.feed(br#"n""#, &mut tokens).unwrap();
+        assert_eq!(1, n);
+        assert_eq!(Token::new(TokenKind::Str, 1, 4), tokens[0]);
+    }
+}
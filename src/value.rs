@@ -0,0 +1,299 @@
+use crate::{Token, TokenKind};
+
+/// A zero-copy, read-only view into a parsed JSON value.
+///
+/// `JsonValue` borrows the original input and the token array produced by
+/// [`JsonParser::parse`](crate::JsonParser::parse) and navigates between
+/// them by reading `Token::size`, without building a DOM or re-scanning
+/// byte spans.
+#[derive(Debug, Copy, Clone)]
+pub struct JsonValue<'a> {
+    js: &'a [u8],
+    tokens: &'a [Token],
+    idx: usize,
+}
+
+impl<'a> JsonValue<'a> {
+    /// Creates a view over the root token (`tokens[0]`) of a parsed document.
+    pub fn new(js: &'a [u8], tokens: &'a [Token]) -> Self {
+        Self { js, tokens, idx: 0 }
+    }
+
+    fn token(&self) -> &'a Token {
+        &self.tokens[self.idx]
+    }
+
+    /// The raw, still-escaped bytes this value's token spans.
+    fn raw(&self) -> &'a [u8] {
+        let t = self.token();
+        &self.js[t.start as usize..t.end as usize]
+    }
+
+    /// The kind of JSON value this is.
+    pub fn kind(&self) -> TokenKind {
+        self.token().kind
+    }
+
+    /// Whether this value is the JSON literal `null`.
+    pub fn is_null(&self) -> bool {
+        self.kind() == TokenKind::Primitive && self.raw() == b"null"
+    }
+
+    /// Interprets this value as `true`/`false`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.raw() {
+            b"true" => Some(true),
+            b"false" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Parses this value as an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        if self.kind() != TokenKind::Primitive {
+            return None;
+        }
+        core::str::from_utf8(self.raw()).ok()?.parse().ok()
+    }
+
+    /// Parses this value as an `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        if self.kind() != TokenKind::Primitive {
+            return None;
+        }
+        core::str::from_utf8(self.raw()).ok()?.parse().ok()
+    }
+
+    /// Unescapes this JSON string into `buf`, returning the decoded `&str`.
+    ///
+    /// Handles `\" \\ \/ \b \f \n \r \t` and `\uXXXX` escapes, joining a
+    /// `\uXXXX` high surrogate with an immediately following low surrogate
+    /// into the one scalar value they encode (e.g. an emoji outside the
+    /// BMP, written as a surrogate pair). Returns `None` if this isn't a
+    /// string, `buf` is too small, an escape is invalid, or a surrogate is
+    /// unpaired.
+    pub fn as_str<'b>(&self, buf: &'b mut [u8]) -> Option<&'b str> {
+        if self.kind() != TokenKind::Str {
+            return None;
+        }
+        let len = unescape(self.raw(), buf)?;
+        core::str::from_utf8(&buf[..len]).ok()
+    }
+
+    /// Looks up `key` in this object's members.
+    ///
+    /// Returns `None` if this value is not an object, or it has no member
+    /// named `key`. Key comparison is on raw (still-escaped) bytes, so a
+    /// key written with an escape sequence (such as a `u` escape for a
+    /// plain letter) won't match its unescaped spelling.
+    pub fn get(&self, key: &str) -> Option<JsonValue<'a>> {
+        if self.kind() != TokenKind::Object {
+            return None;
+        }
+        let mut key_idx = self.idx + 1;
+        for _ in 0..self.token().size {
+            let key_tok = &self.tokens[key_idx];
+            let value_idx = key_idx + 1;
+            let key_bytes = &self.js[key_tok.start as usize..key_tok.end as usize];
+            if key_tok.kind == TokenKind::Str && key_bytes == key.as_bytes() {
+                return Some(JsonValue {
+                    js: self.js,
+                    tokens: self.tokens,
+                    idx: value_idx,
+                });
+            }
+            key_idx = value_idx + subtree_len(self.tokens, value_idx);
+        }
+        None
+    }
+
+    /// Looks up the `i`-th element of this array.
+    ///
+    /// Returns `None` if this value is not an array, or `i` is out of range.
+    pub fn index(&self, i: usize) -> Option<JsonValue<'a>> {
+        if self.kind() != TokenKind::Array || i >= self.token().size {
+            return None;
+        }
+        let mut elem_idx = self.idx + 1;
+        for _ in 0..i {
+            elem_idx += subtree_len(self.tokens, elem_idx);
+        }
+        Some(JsonValue {
+            js: self.js,
+            tokens: self.tokens,
+            idx: elem_idx,
+        })
+    }
+}
+
+/// Number of tokens `tokens[idx]` and its descendants occupy, computed from
+/// `Token::size` rather than re-scanning byte spans.
+fn subtree_len(tokens: &[Token], idx: usize) -> usize {
+    let mut len = 1;
+    let mut child = idx + 1;
+    for _ in 0..tokens[idx].size {
+        let child_len = subtree_len(tokens, child);
+        len += child_len;
+        child += child_len;
+    }
+    len
+}
+
+/// Unescapes a JSON string body (without the surrounding quotes) into `out`,
+/// returning the number of bytes written.
+fn unescape(src: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    let mut i = 0;
+    while i < src.len() {
+        let c = src[i];
+        if c != b'\\' {
+            *out.get_mut(len)? = c;
+            len += 1;
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let literal = match *src.get(i)? {
+            b'"' => b'"',
+            b'\\' => b'\\',
+            b'/' => b'/',
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'u' => {
+                let code = parse_hex4(src.get(i + 1..i + 5)?)?;
+                i += 5;
+                let scalar = if (0xD800..=0xDBFF).contains(&code) {
+                    // High surrogate: must be immediately followed by a low
+                    // surrogate `\uXXXX` escape to form one scalar value.
+                    if src.get(i) != Some(&b'\\') || src.get(i + 1) != Some(&b'u') {
+                        return None;
+                    }
+                    let low = parse_hex4(src.get(i + 2..i + 6)?)?;
+                    if !(0xDC00..=0xDFFF).contains(&low) {
+                        return None;
+                    }
+                    i += 6;
+                    0x10000 + ((code as u32 - 0xD800) << 10) + (low as u32 - 0xDC00)
+                } else if (0xDC00..=0xDFFF).contains(&code) {
+                    // Lone low surrogate, not preceded by a high one.
+                    return None;
+                } else {
+                    code as u32
+                };
+                let ch = char::from_u32(scalar)?;
+                let mut encode_buf = [0u8; 4];
+                let encoded = ch.encode_utf8(&mut encode_buf).as_bytes();
+                out.get_mut(len..len + encoded.len())?
+                    .copy_from_slice(encoded);
+                len += encoded.len();
+                continue;
+            }
+            _ => return None,
+        };
+        *out.get_mut(len)? = literal;
+        len += 1;
+        i += 1;
+    }
+    Some(len)
+}
+
+/// Parses 4 ASCII hex digits into a `u16`, as used by `\uXXXX` escapes.
+fn parse_hex4(digits: &[u8]) -> Option<u16> {
+    let mut value: u16 = 0;
+    for &b in digits {
+        let digit = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            _ => return None,
+        };
+        value = value * 16 + digit as u16;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JsonParser;
+
+    fn parse<'a>(js: &'a [u8], tokens: &'a mut [Token]) -> JsonValue<'a> {
+        let mut parser = JsonParser::new();
+        parser.parse(js, tokens).unwrap();
+        JsonValue::new(js, tokens)
+    }
+
+    #[test]
+    fn get_and_index() {
+        let js = br#"{"a": 1, "b": [2, 3], "c": {"d": 4}}"#;
+        let mut tokens = [Token::default(); 11];
+        let root = parse(js, &mut tokens);
+
+        assert_eq!(Some(1), root.get("a").and_then(|v| v.as_i64()));
+        assert_eq!(Some(3), root.get("b").and_then(|v| v.index(1)?.as_i64()));
+        assert_eq!(
+            Some(4),
+            root.get("c").and_then(|v| v.get("d")?.as_i64())
+        );
+        assert!(root.get("missing").is_none());
+        assert!(root.get("b").unwrap().index(5).is_none());
+    }
+
+    #[test]
+    fn as_bool_and_null() {
+        let js = br#"[true, false, null]"#;
+        let mut tokens = [Token::default(); 4];
+        let root = parse(js, &mut tokens);
+
+        assert_eq!(Some(true), root.index(0).unwrap().as_bool());
+        assert_eq!(Some(false), root.index(1).unwrap().as_bool());
+        assert!(root.index(2).unwrap().is_null());
+    }
+
+    #[test]
+    fn as_f64() {
+        let js = br#"3.25"#;
+        let mut tokens = [Token::default(); 1];
+        let root = parse(js, &mut tokens);
+        assert_eq!(Some(3.25), root.as_f64());
+    }
+
+    #[test]
+    fn as_str_unescapes() {
+        let js = br#""a\nb\tc!""#;
+        let mut tokens = [Token::default(); 1];
+        let root = parse(js, &mut tokens);
+        let mut buf = [0u8; 16];
+        assert_eq!(Some("a\nb\tc!"), root.as_str(&mut buf));
+    }
+
+    #[test]
+    fn as_str_joins_surrogate_pair() {
+        let js = "\"\\uD83D\\uDE00\"".as_bytes();
+        let mut tokens = [Token::default(); 1];
+        let root = parse(js, &mut tokens);
+        let mut buf = [0u8; 8];
+        assert_eq!(Some("\u{1f600}"), root.as_str(&mut buf));
+    }
+
+    #[test]
+    fn as_str_rejects_lone_surrogate() {
+        let js = br#""\uD83D""#;
+        let mut tokens = [Token::default(); 1];
+        let root = parse(js, &mut tokens);
+        let mut buf = [0u8; 8];
+        assert!(root.as_str(&mut buf).is_none());
+    }
+
+    #[test]
+    fn as_str_buffer_too_small() {
+        let js = br#""hello""#;
+        let mut tokens = [Token::default(); 1];
+        let root = parse(js, &mut tokens);
+        let mut buf = [0u8; 2];
+        assert!(root.as_str(&mut buf).is_none());
+    }
+}